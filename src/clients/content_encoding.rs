@@ -0,0 +1,125 @@
+//! Content-encoding selection for outgoing push payloads.
+//!
+//! [`request_builder`](crate::clients::request_builder) emits `aes128gcm`
+//! (RFC 8188) bodies by default. Some endpoints still only understand the
+//! older draft `aesgcm` encoding, which moves the salt and sender public key
+//! out of the body and into `Encryption`/`Crypto-Key` headers, and uses a
+//! different HKDF `info` construction. Selecting
+//! [`ContentEncoding::AesGcm`] on a `WebPushMessageBuilder` switches
+//! `build_request` to that framing for the message.
+
+use ct_codecs::{Base64UrlSafeNoPadding, Encoder};
+use http::{HeaderMap, HeaderValue};
+
+/// Which push content-encoding scheme a message should be sent with.
+///
+/// Defaults to [`ContentEncoding::Aes128Gcm`], the only encoding described by
+/// the current Push API / RFC 8188. [`ContentEncoding::AesGcm`] targets older
+/// user agents that only implement the earlier IETF draft.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentEncoding {
+    /// RFC 8188 `aes128gcm`: salt, record size and sender public key are
+    /// carried inline in the body.
+    #[default]
+    Aes128Gcm,
+    /// The legacy draft `aesgcm` encoding: salt and sender public key are
+    /// carried in the `Encryption` and `Crypto-Key` headers instead.
+    AesGcm,
+}
+
+impl ContentEncoding {
+    /// The value to send in the `Content-Encoding` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Aes128Gcm => "aes128gcm",
+            ContentEncoding::AesGcm => "aesgcm",
+        }
+    }
+}
+
+/// Builds the `Encryption` and `Crypto-Key` headers `aesgcm` needs, since
+/// unlike `aes128gcm` it cannot carry the salt and sender public key inline
+/// in the body.
+pub fn aesgcm_headers(salt: &[u8], as_public: &[u8]) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+
+    let encryption = format!("salt={}", base64_url(salt));
+    let crypto_key = format!("dh={}", base64_url(as_public));
+
+    headers.insert("Encryption", HeaderValue::from_str(&encryption).expect("base64 is valid ascii"));
+    headers.insert("Crypto-Key", HeaderValue::from_str(&crypto_key).expect("base64 is valid ascii"));
+
+    headers
+}
+
+/// The HKDF `info` block for the legacy `aesgcm` encoding: the literal
+/// `Content-Encoding` label plus a `P-256` context block containing both
+/// public keys, as specified by `draft-ietf-webpush-encryption` (distinct
+/// from RFC 8291's simpler `WebPush: info` block used for `aes128gcm`).
+pub fn aesgcm_info(label: &str, ua_public: &[u8], as_public: &[u8]) -> Vec<u8> {
+    let mut info = Vec::with_capacity(label.len() + 8 + 5 + 1 + 4 + ua_public.len() + as_public.len());
+
+    info.extend_from_slice(format!("Content-Encoding: {label}\0").as_bytes());
+    info.extend_from_slice(b"P-256\0");
+    info.extend_from_slice(&(ua_public.len() as u16).to_be_bytes());
+    info.extend_from_slice(ua_public);
+    info.extend_from_slice(&(as_public.len() as u16).to_be_bytes());
+    info.extend_from_slice(as_public);
+
+    info
+}
+
+fn base64_url(bytes: &[u8]) -> String {
+    Base64UrlSafeNoPadding::encode_to_string(bytes).expect("base64 encoding of a byte slice does not fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aesgcm_info_matches_the_draft_layout() {
+        let ua_public = [0x11u8; 4];
+        let as_public = [0x22u8; 3];
+
+        let info = aesgcm_info("aesgcm", &ua_public, &as_public);
+
+        let mut expected = Vec::new();
+        expected.extend_from_slice(b"Content-Encoding: aesgcm\0");
+        expected.extend_from_slice(b"P-256\0");
+        expected.extend_from_slice(&4u16.to_be_bytes());
+        expected.extend_from_slice(&ua_public);
+        expected.extend_from_slice(&3u16.to_be_bytes());
+        expected.extend_from_slice(&as_public);
+
+        assert_eq!(info, expected);
+    }
+
+    #[test]
+    fn aesgcm_info_embeds_key_lengths_as_big_endian_u16() {
+        let ua_public = [0xAAu8; 65];
+        let as_public = [0xBBu8; 65];
+
+        let info = aesgcm_info("aesgcm", &ua_public, &as_public);
+
+        let ua_len_offset = "Content-Encoding: aesgcm\0P-256\0".len();
+        assert_eq!(&info[ua_len_offset..ua_len_offset + 2], &65u16.to_be_bytes());
+
+        let as_len_offset = ua_len_offset + 2 + ua_public.len();
+        assert_eq!(&info[as_len_offset..as_len_offset + 2], &65u16.to_be_bytes());
+    }
+
+    #[test]
+    fn aesgcm_info_differs_when_either_key_differs() {
+        let as_public = [0x01u8; 65];
+
+        let info_a = aesgcm_info("aesgcm", &[0xAAu8; 65], &as_public);
+        let info_b = aesgcm_info("aesgcm", &[0xBBu8; 65], &as_public);
+
+        assert_ne!(info_a, info_b);
+
+        let info_c = aesgcm_info("aesgcm", &[0xAAu8; 65], &[0x02u8; 65]);
+
+        assert_ne!(info_a, info_c);
+    }
+}
@@ -87,11 +87,16 @@ impl WebPushClient for HyperWebPushClient {
         debug!("Response: {:?}", response);
 
         if let Err(WebPushError::ServerError {
+            status,
             retry_after: None,
             info,
         }) = response
         {
-            Err(WebPushError::ServerError { retry_after, info })
+            Err(WebPushError::ServerError {
+                status,
+                retry_after,
+                info,
+            })
         } else {
             Ok(response?)
         }
@@ -7,11 +7,16 @@ use async_trait::async_trait;
 
 use crate::{WebPushError, WebPushMessage};
 
+pub mod content_encoding;
 pub mod request_builder;
+pub mod retry;
 
 #[cfg(feature = "hyper-client")]
 pub mod hyper_client;
 
+#[cfg(feature = "ohttp-client")]
+pub mod ohttp;
+
 const MAX_RESPONSE_SIZE: usize = 64 * 1024;
 
 /// An async client for sending the notification payload.
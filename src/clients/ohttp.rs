@@ -0,0 +1,253 @@
+//! A [`WebPushClient`] that delivers notifications through an Oblivious HTTP
+//! (OHTTP) relay, so the relay operator can see neither which push endpoint
+//! is being contacted nor the encrypted payload.
+//!
+//! The request built by [`request_builder::build_request`] is serialized to
+//! Binary HTTP ([RFC 9292](https://datatracker.ietf.org/doc/html/rfc9292)),
+//! HPKE-sealed against the configured gateway
+//! ([RFC 9458](https://datatracker.ietf.org/doc/html/rfc9458) /
+//! [RFC 9180](https://datatracker.ietf.org/doc/html/rfc9180)), and POSTed to
+//! the relay as `message/ohttp-req`. The `message/ohttp-res` reply is opened
+//! and fed back into [`request_builder::parse_response`], so everything past
+//! encapsulation reuses the same send/parse pipeline as
+//! [`HyperWebPushClient`](crate::clients::hyper_client::HyperWebPushClient).
+
+use async_trait::async_trait;
+use bhttp::{Message, Mode};
+use bytes::Bytes;
+use http::{header::RETRY_AFTER, Request};
+use http_body_util::{BodyExt, Full};
+use hyper_tls::HttpsConnector;
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::TokioExecutor,
+};
+use ohttp::ClientRequest;
+
+use crate::{
+    clients::{request_builder, WebPushClient, MAX_RESPONSE_SIZE},
+    error::{RetryAfter, WebPushError},
+    message::WebPushMessage,
+};
+
+/// Identifies the OHTTP gateway a message should be relayed to.
+#[derive(Clone)]
+pub struct OhttpGatewayConfig {
+    /// The relay's URL, which receives the encapsulated request and never
+    /// sees the push endpoint or payload.
+    pub relay_url: String,
+    /// The gateway's encoded key configuration (KEM/KDF/AEAD identifiers plus
+    /// its public key), as published by the gateway operator.
+    pub key_config: Vec<u8>,
+}
+
+/// An async client that relays notifications through an OHTTP relay to hide
+/// the push target and payload from it.
+///
+/// Like [`HyperWebPushClient`](crate::clients::hyper_client::HyperWebPushClient),
+/// this client is thread-safe and cheap to clone.
+#[derive(Clone)]
+pub struct OhttpWebPushClient {
+    client: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
+    gateway: OhttpGatewayConfig,
+}
+
+impl OhttpWebPushClient {
+    /// Creates a new client relaying through the given gateway.
+    pub fn new(gateway: OhttpGatewayConfig) -> Self {
+        Self {
+            client: Client::builder(TokioExecutor::new()).build(HttpsConnector::new()),
+            gateway,
+        }
+    }
+}
+
+#[async_trait]
+impl WebPushClient for OhttpWebPushClient {
+    /// Sends a notification through the configured OHTTP relay. Never times out.
+    async fn send(&self, message: WebPushMessage) -> Result<(), WebPushError> {
+        let request: Request<Full<Bytes>> = request_builder::build_request(message);
+
+        let binary_request = to_binary_http(request).await?;
+
+        let client_request = ClientRequest::from_encoded(&self.gateway.key_config)
+            .map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+        let (encapsulated, response_context) = client_request
+            .encapsulate(&binary_request)
+            .map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+        let relay_request = Request::post(&self.gateway.relay_url)
+            .header("content-type", "message/ohttp-req")
+            .body(Full::new(Bytes::from(encapsulated)))
+            .map_err(|_| WebPushError::InvalidUri)?;
+
+        let relay_response = self.client.request(relay_request).await?;
+
+        if !relay_response.status().is_success() {
+            return Err(WebPushError::ServerError {
+                status: relay_response.status(),
+                retry_after: relay_response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|ra| ra.to_str().ok())
+                    .and_then(RetryAfter::from_str),
+                info: format!("OHTTP relay returned {}", relay_response.status()),
+            });
+        }
+
+        let mut encapsulated_response = Vec::new();
+        let mut body = relay_response.into_body();
+        while let Some(chunk) = body.frame().await {
+            if let Some(data) = chunk?.data_ref() {
+                encapsulated_response.extend_from_slice(data);
+                if encapsulated_response.len() > MAX_RESPONSE_SIZE {
+                    return Err(WebPushError::ResponseTooLarge);
+                }
+            }
+        }
+
+        let binary_response = response_context
+            .decapsulate(&encapsulated_response)
+            .map_err(|_| WebPushError::InvalidOhttpResponse)?;
+
+        let (status, headers, response_body) = from_binary_http(&binary_response)?;
+
+        // The push service's own `Retry-After` travels inside the decapsulated
+        // response, not on the (opaque, metadata-free) outer relay response.
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|ra| ra.to_str().ok())
+            .and_then(RetryAfter::from_str);
+
+        let response = request_builder::parse_response(status, response_body);
+
+        if let Err(WebPushError::ServerError {
+            status,
+            retry_after: None,
+            info,
+        }) = response
+        {
+            Err(WebPushError::ServerError {
+                status,
+                retry_after,
+                info,
+            })
+        } else {
+            Ok(response?)
+        }
+    }
+}
+
+/// Serializes a built push request into an RFC 9292 Binary HTTP message.
+async fn to_binary_http(request: Request<Full<Bytes>>) -> Result<Vec<u8>, WebPushError> {
+    let (parts, body) = request.into_parts();
+
+    let body_bytes = body
+        .collect()
+        .await
+        .map_err(|_| WebPushError::InvalidCryptoKeys)?
+        .to_bytes();
+
+    let mut message = Message::request(
+        parts.method.as_str().as_bytes().to_vec(),
+        parts.uri.scheme_str().unwrap_or("https").as_bytes().to_vec(),
+        parts.uri.authority().map(|a| a.as_str().as_bytes().to_vec()).unwrap_or_default(),
+        parts
+            .uri
+            .path_and_query()
+            .map(|pq| pq.as_str().as_bytes().to_vec())
+            .unwrap_or_default(),
+    );
+
+    for (name, value) in parts.headers.iter() {
+        message.put_header(name.as_str().as_bytes(), value.as_bytes());
+    }
+
+    message.write_content(&body_bytes);
+
+    let mut encoded = Vec::new();
+    message
+        .write_bhttp(Mode::KnownLength, &mut encoded)
+        .map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+    Ok(encoded)
+}
+
+/// Parses an RFC 9292 Binary HTTP response message back into a status code,
+/// its headers (so callers can recover out-of-band values like
+/// `Retry-After`) and the body.
+fn from_binary_http(encoded: &[u8]) -> Result<(http::StatusCode, http::HeaderMap, Vec<u8>), WebPushError> {
+    let message =
+        Message::read_bhttp(&mut std::io::Cursor::new(encoded)).map_err(|_| WebPushError::InvalidOhttpResponse)?;
+
+    let status = message
+        .control()
+        .status()
+        .and_then(|code| http::StatusCode::from_u16(code).ok())
+        .ok_or(WebPushError::InvalidOhttpResponse)?;
+
+    let mut headers = http::HeaderMap::new();
+    for field in message.header().iter() {
+        if let (Ok(name), Ok(value)) = (
+            http::HeaderName::from_bytes(field.name()),
+            http::HeaderValue::from_bytes(field.value()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+
+    Ok((status, headers, message.content().to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn to_binary_http_round_trips_method_headers_and_body() {
+        let request = Request::post("https://push.example.com/wpush/v1/abc")
+            .header("content-encoding", "aes128gcm")
+            .header("ttl", "60")
+            .body(Full::new(Bytes::from_static(b"ciphertext")))
+            .unwrap();
+
+        let encoded = to_binary_http(request).await.unwrap();
+
+        let message = Message::read_bhttp(&mut std::io::Cursor::new(&encoded[..])).unwrap();
+
+        assert_eq!(message.control().method(), Some(b"POST".as_slice()));
+        assert_eq!(message.control().authority(), Some(b"push.example.com".as_slice()));
+        assert_eq!(message.control().path(), Some(b"/wpush/v1/abc".as_slice()));
+        assert_eq!(
+            message.header().get(b"content-encoding"),
+            Some(b"aes128gcm".as_slice())
+        );
+        assert_eq!(message.header().get(b"ttl"), Some(b"60".as_slice()));
+        assert_eq!(message.content(), b"ciphertext");
+    }
+
+    #[test]
+    fn from_binary_http_round_trips_status_headers_and_body() {
+        let mut message = Message::response(201);
+        message.put_header(b"retry-after", b"120");
+        message.write_content(b"created");
+
+        let mut encoded = Vec::new();
+        message.write_bhttp(Mode::KnownLength, &mut encoded).unwrap();
+
+        let (status, headers, body) = from_binary_http(&encoded).unwrap();
+
+        assert_eq!(status, http::StatusCode::CREATED);
+        assert_eq!(headers.get(RETRY_AFTER).unwrap(), "120");
+        assert_eq!(body, b"created");
+    }
+
+    #[test]
+    fn from_binary_http_rejects_garbage_input() {
+        assert!(matches!(
+            from_binary_http(b"not a binary http message"),
+            Err(WebPushError::InvalidOhttpResponse)
+        ));
+    }
+}
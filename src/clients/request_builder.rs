@@ -0,0 +1,62 @@
+//! Turns a [`WebPushMessage`] into an HTTP request, and a push service's
+//! response back into a `Result`. Shared by every [`WebPushClient`](crate::clients::WebPushClient)
+//! implementation so they only need to differ in how they actually move
+//! bytes over the wire.
+
+use bytes::Bytes;
+use http::{Request, StatusCode};
+use http_body_util::Full;
+
+use crate::{clients::content_encoding::ContentEncoding, error::WebPushError, message::WebPushMessage};
+
+/// Builds the HTTP request for `message`, setting the headers and body
+/// framing appropriate to its [`ContentEncoding`].
+pub fn build_request(message: WebPushMessage) -> Request<Full<Bytes>> {
+    let mut builder = Request::post(message.endpoint.clone()).header("TTL", message.ttl.to_string());
+
+    if let Some(signature) = &message.vapid_signature {
+        builder = builder.header("Authorization", format!("vapid t={}, k={}", signature.auth_t, signature.auth_k));
+    }
+
+    let body = match message.payload {
+        Some(payload) => {
+            builder = builder
+                .header("Content-Encoding", payload.content_encoding.as_str())
+                .header("Content-Type", "application/octet-stream");
+
+            if payload.content_encoding == ContentEncoding::AesGcm {
+                for (name, value) in payload.crypto_headers.iter() {
+                    builder = builder.header(name, value);
+                }
+            }
+
+            Full::new(Bytes::from(payload.content))
+        }
+        None => Full::new(Bytes::new()),
+    };
+
+    builder
+        .body(body)
+        .expect("endpoint and header values were already validated by WebPushMessageBuilder")
+}
+
+/// Interprets a push service's response, turning a non-2xx status into the
+/// matching [`WebPushError`].
+pub fn parse_response(status: StatusCode, body: Vec<u8>) -> Result<(), WebPushError> {
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let info = String::from_utf8_lossy(&body).into_owned();
+
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(WebPushError::Unauthorized),
+        StatusCode::GONE => Err(WebPushError::EndpointNotValid),
+        StatusCode::NOT_FOUND => Err(WebPushError::EndpointNotFound),
+        _ => Err(WebPushError::ServerError {
+            status,
+            retry_after: None,
+            info,
+        }),
+    }
+}
@@ -0,0 +1,166 @@
+//! A generic retry wrapper usable with any [`WebPushClient`] implementation.
+
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use rand::Rng;
+
+use crate::{
+    clients::WebPushClient,
+    error::{RetryAfter, WebPushError},
+    message::WebPushMessage,
+};
+
+/// Configures how [`RetryingClient`] retries a failed send.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of attempts, including the first. Must be at least 1.
+    pub max_attempts: u32,
+    /// Base delay for exponential backoff, used when the server didn't send a `Retry-After`.
+    pub base_delay: Duration,
+    /// Upper bound on any computed backoff delay.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Capped exponential backoff with full jitter for the given (zero-based) attempt.
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let capped = self
+            .base_delay
+            .saturating_mul(1u32.checked_shl(attempt.min(20)).unwrap_or(u32::MAX))
+            .min(self.max_delay);
+
+        let jittered_ms = rand::thread_rng().gen_range(0..=capped.as_millis() as u64);
+
+        Duration::from_millis(jittered_ms)
+    }
+}
+
+/// Wraps any [`WebPushClient`] with automatic retries on transient failures.
+///
+/// A server-supplied `Retry-After` is honored exactly; otherwise the client
+/// falls back to [`RetryConfig`]'s capped exponential backoff with jitter.
+/// Non-retryable outcomes (`Unauthorized`, `EndpointNotValid`,
+/// `EndpointNotFound`) short-circuit immediately without consuming an
+/// attempt's delay. After the configured number of attempts is exhausted,
+/// the last error (with its `retry_after`, if any) is returned as-is.
+#[derive(Clone)]
+pub struct RetryingClient<C> {
+    inner: C,
+    config: RetryConfig,
+}
+
+impl<C: WebPushClient> RetryingClient<C> {
+    /// Wraps `inner` with the given retry configuration.
+    pub fn new(inner: C, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+}
+
+#[async_trait]
+impl<C: WebPushClient + Sync> WebPushClient for RetryingClient<C> {
+    /// Sends a notification, retrying on transient failures per this client's [`RetryConfig`].
+    async fn send(&self, message: WebPushMessage) -> Result<(), WebPushError> {
+        let mut attempt = 0;
+
+        loop {
+            match self.inner.send(message.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(err) if attempt + 1 < self.config.max_attempts && is_retryable(&err) => {
+                    tokio::time::sleep(retry_delay(&self.config, attempt, &err)).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Only retries what's actually transient: a 5xx [`ServerError`](WebPushError::ServerError)
+/// or a connection-level failure. Every other variant (a 4xx-derived
+/// `ServerError`, invalid/expired endpoints, crypto errors, ...) is
+/// permanent and defaults to non-retryable.
+fn is_retryable(error: &WebPushError) -> bool {
+    match error {
+        WebPushError::ServerError { status, .. } => status.is_server_error(),
+        WebPushError::Io(_) | WebPushError::Other(_) => true,
+        _ => false,
+    }
+}
+
+fn retry_delay(config: &RetryConfig, attempt: u32, error: &WebPushError) -> Duration {
+    match error {
+        WebPushError::ServerError {
+            retry_after: Some(retry_after),
+            ..
+        } => match retry_after {
+            RetryAfter::Delay(duration) => *duration,
+            RetryAfter::DateTime(at) => at.duration_since(SystemTime::now()).unwrap_or_default(),
+        },
+        _ => config.backoff_delay(attempt),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+
+    fn server_error(status: StatusCode) -> WebPushError {
+        WebPushError::ServerError {
+            status,
+            retry_after: None,
+            info: String::new(),
+        }
+    }
+
+    #[test]
+    fn server_errors_and_connection_errors_are_retryable() {
+        assert!(is_retryable(&server_error(StatusCode::INTERNAL_SERVER_ERROR)));
+        assert!(is_retryable(&server_error(StatusCode::SERVICE_UNAVAILABLE)));
+        assert!(is_retryable(&WebPushError::Other("connection reset".into())));
+    }
+
+    #[test]
+    fn permanent_errors_default_to_non_retryable() {
+        assert!(!is_retryable(&WebPushError::Unauthorized));
+        assert!(!is_retryable(&WebPushError::EndpointNotValid));
+        assert!(!is_retryable(&WebPushError::EndpointNotFound));
+        // Any variant not explicitly listed (e.g. a crypto/validation error)
+        // must default to non-retryable, not the other way around.
+        assert!(!is_retryable(&WebPushError::InvalidCryptoKeys));
+    }
+
+    #[test]
+    fn server_error_retries_only_on_5xx_not_4xx() {
+        assert!(!is_retryable(&server_error(StatusCode::BAD_REQUEST)));
+        assert!(!is_retryable(&server_error(StatusCode::PAYLOAD_TOO_LARGE)));
+        assert!(!is_retryable(&server_error(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(is_retryable(&server_error(StatusCode::BAD_GATEWAY)));
+    }
+
+    #[test]
+    fn backoff_delay_is_capped_and_jittered_within_range() {
+        let config = RetryConfig {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+        };
+
+        for attempt in 0..10 {
+            let delay = config.backoff_delay(attempt);
+            assert!(delay <= config.max_delay);
+        }
+    }
+}
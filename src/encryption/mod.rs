@@ -0,0 +1,4 @@
+//! Contains the receive-side counterpart to the crate's `aes128gcm` push
+//! encryption: generating subscriber keys and decrypting incoming payloads.
+
+pub mod receiver;
@@ -0,0 +1,253 @@
+//! Subscriber-side key generation and payload decryption.
+//!
+//! This is the mirror image of the encryption the crate performs when
+//! sending: it lets a receiver (or a testing harness standing in for one)
+//! generate the `p256dh`/`auth` pair advertised in a push subscription, and
+//! later decrypt an `aes128gcm` push body against them, per
+//! [RFC 8291](https://datatracker.ietf.org/doc/html/rfc8291) and the
+//! [RFC 8188](https://datatracker.ietf.org/doc/html/rfc8188) framing it's
+//! carried in.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes128Gcm, Nonce,
+};
+use ct_codecs::{Base64UrlSafeNoPadding, Decoder, Encoder};
+use hkdf::Hkdf;
+use p256::{ecdh::diffie_hellman, elliptic_curve::sec1::ToEncodedPoint, PublicKey, SecretKey};
+use rand::RngCore;
+use sha2::Sha256;
+
+use crate::error::WebPushError;
+
+const HEADER_LEN: usize = 16 + 4 + 1;
+const TAG_LEN: usize = 16;
+
+/// A subscriber's P-256 key pair and shared auth secret.
+///
+/// These are the values a push subscriber hands to the application server as
+/// `p256dh`/`auth`, and are produced entirely in-process with
+/// [`SubscriberKeys::generate`]. Keep `private_key` around to later
+/// [`decrypt`] payloads sent to this subscription.
+pub struct SubscriberKeys {
+    /// URL-safe base64, unpadded: the uncompressed public key (`p256dh`).
+    pub p256dh: String,
+    /// URL-safe base64, unpadded: the 16-byte shared auth secret (`auth`).
+    pub auth: String,
+    /// The private key matching `p256dh`.
+    pub private_key: SecretKey,
+}
+
+impl SubscriberKeys {
+    /// Generates a fresh P-256 key pair and a random 16-byte `auth` secret.
+    pub fn generate() -> SubscriberKeys {
+        let private_key = SecretKey::random(&mut rand::rngs::OsRng);
+        let public_key = private_key.public_key();
+
+        let mut auth = [0u8; 16];
+        rand::rngs::OsRng.fill_bytes(&mut auth);
+
+        SubscriberKeys {
+            p256dh: Base64UrlSafeNoPadding::encode_to_string(public_key.to_encoded_point(false).as_bytes())
+                .expect("base64 encoding of a fixed-size buffer cannot fail"),
+            auth: Base64UrlSafeNoPadding::encode_to_string(auth)
+                .expect("base64 encoding of a fixed-size buffer cannot fail"),
+            private_key,
+        }
+    }
+}
+
+/// Decrypts an `aes128gcm` push body using the subscriber's private key and
+/// `auth` secret.
+///
+/// `auth` is the same URL-safe base64 string advertised as the
+/// subscription's `auth` key. Returns the decrypted application payload.
+pub fn decrypt(private_key: &SecretKey, auth: &str, body: &[u8]) -> Result<Vec<u8>, WebPushError> {
+    if body.len() < HEADER_LEN {
+        return Err(WebPushError::InvalidCryptoKeys);
+    }
+
+    let salt = &body[0..16];
+    let record_size = u32::from_be_bytes(body[16..20].try_into().unwrap()) as usize;
+    let key_id_len = body[20] as usize;
+
+    let header_end = HEADER_LEN + key_id_len;
+    if body.len() < header_end || record_size == 0 {
+        return Err(WebPushError::InvalidCryptoKeys);
+    }
+
+    let as_public_bytes = &body[HEADER_LEN..header_end];
+    let as_public =
+        PublicKey::from_sec1_bytes(as_public_bytes).map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+    let auth_secret = Base64UrlSafeNoPadding::decode_to_vec(auth, None).map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+    let shared_secret = diffie_hellman(private_key.to_nonzero_scalar(), as_public.as_affine());
+
+    let ua_public = private_key.public_key().to_encoded_point(false);
+    let as_public_encoded = as_public.to_encoded_point(false);
+
+    let mut ikm_info = Vec::with_capacity(16 + ua_public.as_bytes().len() + as_public_encoded.as_bytes().len());
+    ikm_info.extend_from_slice(b"WebPush: info\0");
+    ikm_info.extend_from_slice(ua_public.as_bytes());
+    ikm_info.extend_from_slice(as_public_encoded.as_bytes());
+
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&auth_secret), shared_secret.raw_secret_bytes().as_slice())
+        .expand(&ikm_info, &mut ikm)
+        .map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+    let prk = Hkdf::<Sha256>::new(Some(salt), &ikm);
+
+    let mut cek = [0u8; 16];
+    prk.expand(b"Content-Encoding: aes128gcm\0", &mut cek)
+        .map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+    let mut nonce_base = [0u8; 12];
+    prk.expand(b"Content-Encoding: nonce\0", &mut nonce_base)
+        .map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+    let cipher = Aes128Gcm::new_from_slice(&cek).map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+    let ciphertext = &body[header_end..];
+    if ciphertext.is_empty() {
+        return Err(WebPushError::InvalidCryptoKeys);
+    }
+
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    let records: Vec<&[u8]> = ciphertext.chunks(record_size).collect();
+
+    for (index, record) in records.iter().enumerate() {
+        if record.len() <= TAG_LEN {
+            return Err(WebPushError::InvalidCryptoKeys);
+        }
+
+        let mut nonce_bytes = nonce_base;
+        let counter = (index as u64).to_be_bytes();
+        for (byte, counter_byte) in nonce_bytes[6..].iter_mut().zip(counter[2..].iter()) {
+            *byte ^= counter_byte;
+        }
+
+        let decrypted = cipher
+            .decrypt(Nonce::from_slice(&nonce_bytes), *record)
+            .map_err(|_| WebPushError::InvalidCryptoKeys)?;
+
+        let unpadded_end = decrypted
+            .iter()
+            .rposition(|&b| b != 0)
+            .ok_or(WebPushError::InvalidCryptoKeys)?;
+
+        // RFC 8188 §2: every record but the last is delimited by `0x01`, so
+        // the reader can tell there's more to come; only the last record
+        // uses `0x02`.
+        let is_last_record = index + 1 == records.len();
+        let expected_delimiter = if is_last_record { 0x02 } else { 0x01 };
+        if decrypted[unpadded_end] != expected_delimiter {
+            return Err(WebPushError::InvalidCryptoKeys);
+        }
+
+        plaintext.extend_from_slice(&decrypted[..unpadded_end]);
+    }
+
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The sender-side mirror of [`decrypt`], used only here to produce a
+    /// well-formed `aes128gcm` body to round-trip against.
+    fn encrypt(as_private: &SecretKey, ua_public_bytes: &[u8], auth: &str, record_size: usize, chunks: &[&[u8]]) -> Vec<u8> {
+        let ua_public = PublicKey::from_sec1_bytes(ua_public_bytes).unwrap();
+        let as_public = as_private.public_key();
+
+        let mut salt = [0u8; 16];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+
+        let auth_secret = Base64UrlSafeNoPadding::decode_to_vec(auth, None).unwrap();
+        let shared_secret = diffie_hellman(as_private.to_nonzero_scalar(), ua_public.as_affine());
+
+        let ua_public_encoded = ua_public.to_encoded_point(false);
+        let as_public_encoded = as_public.to_encoded_point(false);
+
+        let mut ikm_info = Vec::new();
+        ikm_info.extend_from_slice(b"WebPush: info\0");
+        ikm_info.extend_from_slice(ua_public_encoded.as_bytes());
+        ikm_info.extend_from_slice(as_public_encoded.as_bytes());
+
+        let mut ikm = [0u8; 32];
+        Hkdf::<Sha256>::new(Some(&auth_secret), shared_secret.raw_secret_bytes().as_slice())
+            .expand(&ikm_info, &mut ikm)
+            .unwrap();
+
+        let prk = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+
+        let mut cek = [0u8; 16];
+        prk.expand(b"Content-Encoding: aes128gcm\0", &mut cek).unwrap();
+
+        let mut nonce_base = [0u8; 12];
+        prk.expand(b"Content-Encoding: nonce\0", &mut nonce_base).unwrap();
+
+        let cipher = Aes128Gcm::new_from_slice(&cek).unwrap();
+        let as_public_bytes = as_public_encoded.as_bytes();
+
+        let mut body = Vec::new();
+        body.extend_from_slice(&salt);
+        body.extend_from_slice(&(record_size as u32).to_be_bytes());
+        body.push(as_public_bytes.len() as u8);
+        body.extend_from_slice(as_public_bytes);
+
+        for (index, chunk) in chunks.iter().enumerate() {
+            let is_last = index + 1 == chunks.len();
+            let delimiter = if is_last { 0x02 } else { 0x01 };
+
+            let mut padded = chunk.to_vec();
+            padded.push(delimiter);
+            padded.resize(record_size - TAG_LEN, 0);
+
+            let mut nonce_bytes = nonce_base;
+            let counter = (index as u64).to_be_bytes();
+            for (byte, counter_byte) in nonce_bytes[6..].iter_mut().zip(counter[2..].iter()) {
+                *byte ^= counter_byte;
+            }
+
+            let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce_bytes), padded.as_slice()).unwrap();
+            body.extend_from_slice(&ciphertext);
+        }
+
+        body
+    }
+
+    #[test]
+    fn round_trip_single_record() {
+        let subscriber = SubscriberKeys::generate();
+        let as_private = SecretKey::random(&mut rand::rngs::OsRng);
+        let ua_public_bytes = Base64UrlSafeNoPadding::decode_to_vec(&subscriber.p256dh, None).unwrap();
+
+        let body = encrypt(&as_private, &ua_public_bytes, &subscriber.auth, 4096, &[b"hello, world"]);
+
+        let plaintext = decrypt(&subscriber.private_key, &subscriber.auth, &body).unwrap();
+        assert_eq!(plaintext, b"hello, world");
+    }
+
+    #[test]
+    fn round_trip_multiple_records_uses_0x01_delimiter_for_non_final_records() {
+        let subscriber = SubscriberKeys::generate();
+        let as_private = SecretKey::random(&mut rand::rngs::OsRng);
+        let ua_public_bytes = Base64UrlSafeNoPadding::decode_to_vec(&subscriber.p256dh, None).unwrap();
+
+        // Three chunks become three 32-byte records, so this exercises the
+        // 0x01 (non-final) / 0x02 (final) delimiter split.
+        let body = encrypt(
+            &as_private,
+            &ua_public_bytes,
+            &subscriber.auth,
+            32,
+            &[b"0123456789", b"abcdefghij", b"ZYXWVUTSRQ"],
+        );
+
+        let plaintext = decrypt(&subscriber.private_key, &subscriber.auth, &body).unwrap();
+        assert_eq!(plaintext, b"0123456789abcdefghijZYXWVUTSRQ");
+    }
+}
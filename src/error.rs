@@ -0,0 +1,122 @@
+//! The error type returned by the clients, encryption and VAPID signing in
+//! this crate.
+
+use std::{
+    fmt, io,
+    time::{Duration, SystemTime},
+};
+
+use http::StatusCode;
+
+/// A server-supplied `Retry-After` value, in either of the two forms
+/// permitted by [RFC 9110 §10.2.3](https://datatracker.ietf.org/doc/html/rfc9110#section-10.2.3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryAfter {
+    /// `Retry-After: <delay-seconds>`
+    Delay(Duration),
+    /// `Retry-After: <http-date>`
+    DateTime(SystemTime),
+}
+
+impl RetryAfter {
+    /// Parses a `Retry-After` header value, accepting both the delay-seconds
+    /// and HTTP-date forms. Returns `None` if `value` is neither.
+    pub fn from_str(value: &str) -> Option<Self> {
+        if let Ok(seconds) = value.trim().parse::<u64>() {
+            return Some(RetryAfter::Delay(Duration::from_secs(seconds)));
+        }
+
+        httpdate::parse_http_date(value.trim()).ok().map(RetryAfter::DateTime)
+    }
+}
+
+/// Errors produced while building, sending, or decrypting a web push message.
+#[derive(Debug)]
+pub enum WebPushError {
+    /// The server rejected the request as unauthorized.
+    Unauthorized,
+    /// The push subscription's endpoint URL is permanently invalid and
+    /// should be discarded.
+    EndpointNotValid,
+    /// The push subscription's endpoint was not found and should be
+    /// discarded.
+    EndpointNotFound,
+    /// The server returned an error response. `status` is the real HTTP
+    /// status, so callers (notably [`RetryingClient`](crate::clients::retry::RetryingClient))
+    /// can tell a permanent 4xx apart from a transient 5xx. `retry_after`,
+    /// if present, is the delay the server asked for before trying again.
+    ServerError {
+        status: StatusCode,
+        retry_after: Option<RetryAfter>,
+        info: String,
+    },
+    /// A URI (an endpoint or a relay URL) could not be parsed.
+    InvalidUri,
+    /// Subscription or VAPID key material was malformed or rejected by the
+    /// underlying crypto implementation.
+    InvalidCryptoKeys,
+    /// A VAPID private key was required but none could be found, e.g. no
+    /// matching PEM section.
+    MissingCryptoKeys,
+    /// [`ContentEncoding::AesGcm`](crate::clients::content_encoding::ContentEncoding::AesGcm)
+    /// was selected but `WebPushMessageBuilder::set_aesgcm_crypto_headers`
+    /// was never called, so there's no `Encryption`/`Crypto-Key` header to
+    /// send the salt and sender public key in.
+    MissingAesGcmCryptoHeaders,
+    /// The response body exceeded the maximum size this crate will buffer.
+    ResponseTooLarge,
+    /// An OHTTP response could not be decapsulated or parsed as Binary HTTP,
+    /// or carried a status line this crate couldn't understand.
+    InvalidOhttpResponse,
+    /// An I/O error, e.g. while reading a PEM/DER key.
+    Io(io::Error),
+    /// An error from the underlying HTTP transport.
+    Other(String),
+}
+
+impl fmt::Display for WebPushError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebPushError::Unauthorized => write!(f, "unauthorized"),
+            WebPushError::EndpointNotValid => write!(f, "endpoint is no longer valid and should be removed"),
+            WebPushError::EndpointNotFound => write!(f, "endpoint not found and should be removed"),
+            WebPushError::ServerError { status, info, .. } => write!(f, "server error ({status}): {info}"),
+            WebPushError::InvalidUri => write!(f, "invalid URI"),
+            WebPushError::InvalidCryptoKeys => write!(f, "invalid crypto keys"),
+            WebPushError::MissingCryptoKeys => write!(f, "missing crypto keys"),
+            WebPushError::MissingAesGcmCryptoHeaders => {
+                write!(f, "aesgcm content encoding selected without crypto headers")
+            }
+            WebPushError::ResponseTooLarge => write!(f, "response body too large"),
+            WebPushError::InvalidOhttpResponse => write!(f, "invalid OHTTP response"),
+            WebPushError::Io(err) => write!(f, "I/O error: {err}"),
+            WebPushError::Other(info) => write!(f, "{info}"),
+        }
+    }
+}
+
+impl std::error::Error for WebPushError {}
+
+impl From<io::Error> for WebPushError {
+    fn from(err: io::Error) -> Self {
+        WebPushError::Io(err)
+    }
+}
+
+impl From<http::uri::InvalidUri> for WebPushError {
+    fn from(_: http::uri::InvalidUri) -> Self {
+        WebPushError::InvalidUri
+    }
+}
+
+impl From<hyper_util::client::legacy::Error> for WebPushError {
+    fn from(err: hyper_util::client::legacy::Error) -> Self {
+        WebPushError::Other(err.to_string())
+    }
+}
+
+impl From<hyper::Error> for WebPushError {
+    fn from(err: hyper::Error) -> Self {
+        WebPushError::Other(err.to_string())
+    }
+}
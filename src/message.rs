@@ -0,0 +1,201 @@
+//! The notification payload and its delivery options, assembled into a
+//! [`WebPushMessage`] that `request_builder::build_request` turns into an
+//! HTTP request.
+
+use http::{HeaderMap, Uri};
+use serde::{Deserialize, Serialize};
+
+use crate::{clients::content_encoding::ContentEncoding, error::WebPushError, vapid::VapidSignature};
+
+/// The key material a push subscription hands the application server, as
+/// used by [`SubscriptionInfo`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+/// A push subscription as returned by `PushManager.subscribe()` in the
+/// browser: the endpoint to POST to, plus the subscriber's key material.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SubscriptionInfo {
+    pub endpoint: String,
+    pub keys: SubscriptionKeys,
+}
+
+impl SubscriptionInfo {
+    /// Builds a `SubscriptionInfo` from its three constituent values.
+    pub fn new<S: Into<String>>(endpoint: S, p256dh: S, auth: S) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            keys: SubscriptionKeys {
+                p256dh: p256dh.into(),
+                auth: auth.into(),
+            },
+        }
+    }
+}
+
+/// An already-encrypted push payload, plus the framing `build_request`
+/// needs to send it under its [`ContentEncoding`].
+#[derive(Clone)]
+pub struct WebPushPayload {
+    /// The ciphertext to send as the request body.
+    pub content: Vec<u8>,
+    /// Which content-encoding scheme `content` is framed for.
+    pub content_encoding: ContentEncoding,
+    /// For [`ContentEncoding::AesGcm`], the `Encryption`/`Crypto-Key`
+    /// headers carrying the salt and sender public key that
+    /// [`ContentEncoding::Aes128Gcm`] instead carries inline in the body.
+    /// Empty for `Aes128Gcm`.
+    pub crypto_headers: HeaderMap,
+}
+
+/// A fully assembled web push message, ready to be turned into an HTTP
+/// request by `request_builder::build_request`.
+///
+/// `Clone`, since [`RetryingClient`](crate::clients::retry::RetryingClient)
+/// needs to resend the same message across attempts.
+#[derive(Clone)]
+pub struct WebPushMessage {
+    pub endpoint: Uri,
+    pub ttl: u32,
+    pub payload: Option<WebPushPayload>,
+    pub vapid_signature: Option<VapidSignature>,
+}
+
+/// Builds a [`WebPushMessage`] from a subscription, an optional payload and
+/// delivery options.
+pub struct WebPushMessageBuilder<'a> {
+    subscription_info: &'a SubscriptionInfo,
+    payload: Option<&'a [u8]>,
+    content_encoding: ContentEncoding,
+    aesgcm_crypto_headers: Option<HeaderMap>,
+    ttl: u32,
+    vapid_signature: Option<VapidSignature>,
+}
+
+impl<'a> WebPushMessageBuilder<'a> {
+    /// Creates a new builder for `subscription_info`.
+    pub fn new(subscription_info: &'a SubscriptionInfo) -> Self {
+        Self {
+            subscription_info,
+            payload: None,
+            content_encoding: ContentEncoding::default(),
+            aesgcm_crypto_headers: None,
+            ttl: 0,
+            vapid_signature: None,
+        }
+    }
+
+    /// Sets the (already-encrypted) notification payload and the
+    /// content-encoding it's framed for. Defaults to
+    /// [`ContentEncoding::Aes128Gcm`] if never called.
+    ///
+    /// For [`ContentEncoding::AesGcm`], also call
+    /// [`Self::set_aesgcm_crypto_headers`] with the message's salt and
+    /// sender public key, since that encoding carries them in headers
+    /// rather than inline in `content`.
+    pub fn set_payload(&mut self, content_encoding: ContentEncoding, content: &'a [u8]) {
+        self.content_encoding = content_encoding;
+        self.payload = Some(content);
+    }
+
+    /// Sets the `Encryption`/`Crypto-Key` headers a
+    /// [`ContentEncoding::AesGcm`] payload needs. Ignored for
+    /// `Aes128Gcm`, which carries the same values inline in the body.
+    pub fn set_aesgcm_crypto_headers(&mut self, salt: &[u8], as_public: &[u8]) {
+        self.aesgcm_crypto_headers = Some(crate::clients::content_encoding::aesgcm_headers(salt, as_public));
+    }
+
+    /// Sets the TTL, in seconds, that the push service should retain the
+    /// notification for if the subscriber is offline.
+    pub fn set_ttl(&mut self, ttl: u32) {
+        self.ttl = ttl;
+    }
+
+    /// Sets the VAPID signature to identify this application server to the
+    /// push service.
+    pub fn set_vapid_signature(&mut self, signature: VapidSignature) {
+        self.vapid_signature = Some(signature);
+    }
+
+    /// Builds the [`WebPushMessage`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WebPushError::MissingAesGcmCryptoHeaders`] if
+    /// [`ContentEncoding::AesGcm`] was selected but
+    /// [`Self::set_aesgcm_crypto_headers`] was never called.
+    pub fn build(self) -> Result<WebPushMessage, WebPushError> {
+        let endpoint: Uri = self.subscription_info.endpoint.parse()?;
+
+        let payload = self
+            .payload
+            .map(|content| {
+                let crypto_headers = match self.content_encoding {
+                    ContentEncoding::Aes128Gcm => HeaderMap::new(),
+                    ContentEncoding::AesGcm => self
+                        .aesgcm_crypto_headers
+                        .ok_or(WebPushError::MissingAesGcmCryptoHeaders)?,
+                };
+
+                Ok(WebPushPayload {
+                    content: content.to_vec(),
+                    content_encoding: self.content_encoding,
+                    crypto_headers,
+                })
+            })
+            .transpose()?;
+
+        Ok(WebPushMessage {
+            endpoint,
+            ttl: self.ttl,
+            payload,
+            vapid_signature: self.vapid_signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription_info() -> SubscriptionInfo {
+        SubscriptionInfo::new(
+            "https://push.example.com/wpush/v1/abc",
+            "BH1HTeKM7-NwaLGHEqxeu2IamQaVVLkcsFHPIHmsCnqxcBHPQBprF41bEMOr3O1hUQ2jU1opNEm1F_lZV_sxMP8",
+            "sBXU5_tIYz-5w7G2B25BEw",
+        )
+    }
+
+    #[test]
+    fn aes128gcm_payload_builds_without_crypto_headers() {
+        let subscription_info = subscription_info();
+        let mut builder = WebPushMessageBuilder::new(&subscription_info);
+        builder.set_payload(ContentEncoding::Aes128Gcm, b"ciphertext");
+
+        let message = builder.build().unwrap();
+        assert!(message.payload.unwrap().crypto_headers.is_empty());
+    }
+
+    #[test]
+    fn aesgcm_payload_without_crypto_headers_is_an_error() {
+        let subscription_info = subscription_info();
+        let mut builder = WebPushMessageBuilder::new(&subscription_info);
+        builder.set_payload(ContentEncoding::AesGcm, b"ciphertext");
+
+        assert!(matches!(builder.build(), Err(WebPushError::MissingAesGcmCryptoHeaders)));
+    }
+
+    #[test]
+    fn aesgcm_payload_with_crypto_headers_builds() {
+        let subscription_info = subscription_info();
+        let mut builder = WebPushMessageBuilder::new(&subscription_info);
+        builder.set_payload(ContentEncoding::AesGcm, b"ciphertext");
+        builder.set_aesgcm_crypto_headers(b"0123456789abcdef", b"a fake sender public key");
+
+        let message = builder.build().unwrap();
+        assert!(!message.payload.unwrap().crypto_headers.is_empty());
+    }
+}
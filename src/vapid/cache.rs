@@ -0,0 +1,187 @@
+//! Caches VAPID JWTs per endpoint origin so a high-volume sender doesn't
+//! re-sign an identical claim set on every message.
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use http::uri::Uri;
+
+use crate::{
+    error::WebPushError,
+    vapid::{signer::Claims, VapidKey, VapidSignature, VapidSigner},
+};
+
+const DEFAULT_TTL_SECS: u64 = 12 * 60 * 60;
+
+/// Wraps a [`VapidKey`] with a cache of previously computed
+/// [`VapidSignature`]s, keyed by endpoint origin (scheme + host), reused
+/// until they near expiry.
+///
+/// `VapidSignatureBuilder::build` signs a fresh JWT on every call even
+/// though its default claims carry a 12-hour `exp` and `aud` only depends on
+/// the endpoint's origin. For a sender fanning out many notifications
+/// against the same push services, this cuts that down to roughly one ES256
+/// signature per origin per TTL window. Messages carrying custom claims are
+/// always signed fresh, since a cached signature for one set of claims must
+/// not be handed back for another.
+pub struct CachingVapidSigner {
+    key: VapidKey,
+    skew: Duration,
+    cache: Mutex<HashMap<String, (VapidSignature, u64)>>,
+}
+
+impl CachingVapidSigner {
+    /// Creates a new cache around `key`. A cached signature is regenerated
+    /// once its `exp` is within `skew` of the current time.
+    pub fn new(key: VapidKey, skew: Duration) -> Self {
+        Self {
+            key,
+            skew,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a signature for `endpoint`, reusing a cached one for the same
+    /// origin when it's still fresh and `claims` carries no custom fields.
+    pub fn sign(&self, endpoint: &Uri, claims: Claims) -> Result<VapidSignature, WebPushError> {
+        if !claims.custom.is_empty() {
+            return VapidSigner::sign(self.key.clone(), endpoint, claims);
+        }
+
+        let origin = origin_key(endpoint)?;
+        let now = unix_now();
+
+        if let Some((signature, exp)) = self.cache.lock().expect("cache mutex poisoned").get(&origin) {
+            if *exp > now + self.skew.as_secs() {
+                return Ok(signature.clone());
+            }
+        }
+
+        // Derive the cached `exp` from the claims actually being signed,
+        // not a hardcoded TTL: a caller signing with a shorter or longer
+        // validity than the default must not have their cache entry outlive
+        // (or be discarded before) the JWT it's holding.
+        let exp = claims
+            .expires_at
+            .map(|expires_at| expires_at.as_secs())
+            .unwrap_or(now + DEFAULT_TTL_SECS);
+        let signature = VapidSigner::sign(self.key.clone(), endpoint, claims)?;
+
+        self.cache
+            .lock()
+            .expect("cache mutex poisoned")
+            .insert(origin, (signature.clone(), exp));
+
+        Ok(signature)
+    }
+}
+
+fn origin_key(endpoint: &Uri) -> Result<String, WebPushError> {
+    let scheme = endpoint.scheme_str().ok_or(WebPushError::InvalidUri)?;
+    let authority = endpoint.authority().ok_or(WebPushError::InvalidUri)?;
+
+    Ok(format!("{scheme}://{authority}"))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::uri::Uri;
+
+    use crate::vapid::VapidSignatureBuilder;
+
+    use super::*;
+
+    fn test_signer(skew: Duration) -> CachingVapidSigner {
+        VapidSignatureBuilder::generate()
+            .into_builder()
+            .unwrap()
+            .into_caching_signer(skew)
+    }
+
+    fn claims() -> Claims {
+        jwt_simple::prelude::Claims::with_custom_claims(
+            std::collections::BTreeMap::new(),
+            jwt_simple::prelude::Duration::from_hours(12),
+        )
+    }
+
+    #[test]
+    fn reuses_a_cached_signature_for_the_same_origin() {
+        let signer = test_signer(Duration::from_secs(60));
+        let endpoint: Uri = "https://push.example.com/wpush/v1/abc".parse().unwrap();
+
+        let first = signer.sign(&endpoint, claims()).unwrap();
+        let second = signer.sign(&endpoint, claims()).unwrap();
+
+        // ECDSA signing is randomized, so byte-identical output can only
+        // happen if the second call returned the cached signature rather
+        // than signing again.
+        assert_eq!(first.auth_t, second.auth_t);
+    }
+
+    #[test]
+    fn resigns_once_the_cached_signature_is_within_skew_of_expiry() {
+        // A skew bigger than the TTL means every lookup treats the cached
+        // signature as already stale, forcing a fresh sign every time.
+        let signer = test_signer(Duration::from_secs(DEFAULT_TTL_SECS + 60));
+        let endpoint: Uri = "https://push.example.com/wpush/v1/abc".parse().unwrap();
+
+        let first = signer.sign(&endpoint, claims()).unwrap();
+        let second = signer.sign(&endpoint, claims()).unwrap();
+
+        assert_ne!(first.auth_t, second.auth_t);
+    }
+
+    #[test]
+    fn derives_cached_expiry_from_the_signed_claims_not_a_hardcoded_ttl() {
+        let signer = test_signer(Duration::from_secs(60));
+        let endpoint: Uri = "https://push.example.com/wpush/v1/abc".parse().unwrap();
+
+        // A 30s validity is far shorter than the default 12h TTL; with a
+        // 60s skew the cache must already consider it stale and resign,
+        // rather than trusting the hardcoded TTL and serving a cache hit
+        // for an already-expired JWT.
+        let short_lived = jwt_simple::prelude::Claims::with_custom_claims(
+            std::collections::BTreeMap::new(),
+            jwt_simple::prelude::Duration::from_secs(30),
+        );
+
+        let first = signer.sign(&endpoint, short_lived.clone()).unwrap();
+        let second = signer.sign(&endpoint, short_lived).unwrap();
+
+        assert_ne!(first.auth_t, second.auth_t);
+    }
+
+    #[test]
+    fn never_caches_signatures_carrying_custom_claims() {
+        let signer = test_signer(Duration::from_secs(60));
+        let endpoint: Uri = "https://push.example.com/wpush/v1/abc".parse().unwrap();
+
+        let mut with_claim = claims();
+        with_claim.custom.insert("sub".into(), "mailto:test@example.com".into());
+
+        let first = signer.sign(&endpoint, with_claim.clone()).unwrap();
+        let second = signer.sign(&endpoint, with_claim).unwrap();
+
+        assert_ne!(first.auth_t, second.auth_t);
+    }
+
+    #[test]
+    fn caches_separately_per_origin() {
+        let signer = test_signer(Duration::from_secs(60));
+        let a: Uri = "https://push.a.example.com/x".parse().unwrap();
+        let b: Uri = "https://push.b.example.com/x".parse().unwrap();
+
+        let sig_a = signer.sign(&a, claims()).unwrap();
+        let sig_b = signer.sign(&b, claims()).unwrap();
+
+        assert_ne!(sig_a.auth_t, sig_b.auth_t);
+    }
+}